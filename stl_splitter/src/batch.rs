@@ -0,0 +1,76 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use rayon::prelude::*;
+
+use crate::cli::Args;
+use crate::mesh::Mesh;
+
+/// Process every STL file matching `pattern` in parallel, saving each
+/// file's slices independently so one bad file can't hold up the rest.
+/// With `--keep-going`, failures are collected and reported at the end
+/// instead of aborting the batch.
+pub fn run(pattern: &str, args: &Args, output_dir: &Path) -> Result<()> {
+    let paths: Vec<PathBuf> = glob::glob(pattern)
+        .with_context(|| format!("invalid glob pattern: {}", pattern))?
+        .filter_map(|entry| entry.ok())
+        .collect();
+
+    if paths.is_empty() {
+        println!("No files matched glob pattern: {}", pattern);
+        return Ok(());
+    }
+
+    println!("Processing {} file(s) matching {}...", paths.len(), pattern);
+
+    let results: Vec<(PathBuf, Result<()>)> = paths
+        .par_iter()
+        .map(|path| (path.clone(), process_file(path, args, output_dir)))
+        .collect();
+
+    let mut failures = Vec::new();
+    for (path, result) in results {
+        match result {
+            Ok(()) => println!("OK: {}", path.display()),
+            Err(err) => failures.push((path, err)),
+        }
+    }
+
+    if failures.is_empty() {
+        return Ok(());
+    }
+
+    println!("\n{} file(s) failed:", failures.len());
+    for (path, err) in &failures {
+        println!("  {}: {:#}", path.display(), err);
+    }
+
+    if args.keep_going {
+        Ok(())
+    } else {
+        Err(anyhow::anyhow!("{} of {} file(s) failed to process", failures.len(), paths.len()))
+    }
+}
+
+/// Load, slice, and save a single STL file according to `args`. Independent
+/// of any other file, so it's safe to run many of these concurrently.
+fn process_file(input_path: &Path, args: &Args, output_dir: &Path) -> Result<()> {
+    let mesh = Mesh::load(input_path)?;
+    let (min, max) = mesh.get_dimensions();
+
+    let axis = args.axis.index();
+    let planes = crate::cut_planes(args, min[axis], max[axis]);
+    let pieces = crate::slice(&mesh, axis, &planes, args.capped);
+
+    let base_name = input_path
+        .file_stem()
+        .context("input file has no stem")?
+        .to_string_lossy();
+
+    for (i, piece) in pieces.iter().enumerate() {
+        let piece_path = output_dir.join(format!("{}_{:03}.stl", base_name, i));
+        Mesh::save(piece, &piece_path)?;
+    }
+
+    Ok(())
+}
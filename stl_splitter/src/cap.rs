@@ -0,0 +1,308 @@
+use stl_io::{Triangle, Vector};
+
+use crate::geometry::{self, EPSILON};
+
+/// A single directed intersection segment where one straddling triangle
+/// crossed the split plane, oriented to match the *upper* polygon's own
+/// cut-boundary direction at that triangle (see `clip_triangle`); segments
+/// are joined into a loop by matching each one's start to the previous
+/// segment's end.
+pub type Segment = [Vector<f32>; 2];
+
+/// Stitch raw, directed intersection segments (one per straddling triangle)
+/// into closed loops by following each segment's start-to-end direction and
+/// matching endpoints within `EPSILON` to absorb the float noise introduced
+/// by the plane-clipping interpolation. Because every segment is oriented
+/// consistently (see `clip_triangle`), the resulting loop threads through in
+/// one stable direction rather than an arbitrary one.
+pub fn stitch_loops(mut segments: Vec<Segment>) -> Vec<Vec<Vector<f32>>> {
+    let mut loops = Vec::new();
+
+    while let Some(seg) = segments.pop() {
+        let mut points = vec![seg[0], seg[1]];
+        let mut closed = false;
+
+        loop {
+            let tail = *points.last().unwrap();
+            if points.len() > 2 && points_close(tail, points[0]) {
+                points.pop();
+                closed = true;
+                break;
+            }
+
+            let next = segments.iter().position(|s| points_close(s[0], tail));
+
+            match next {
+                Some(idx) => {
+                    let s = segments.remove(idx);
+                    points.push(s[1]);
+                }
+                // Open chain: the cut didn't close, so there's nothing sane to cap.
+                None => break,
+            }
+        }
+
+        if closed && points.len() >= 3 {
+            loops.push(points);
+        }
+    }
+
+    loops
+}
+
+fn points_close(a: Vector<f32>, b: Vector<f32>) -> bool {
+    (a[0] - b[0]).abs() < EPSILON && (a[1] - b[1]).abs() < EPSILON && (a[2] - b[2]).abs() < EPSILON
+}
+
+/// Ear-clip a simple polygon lying in the plane perpendicular to `axis`,
+/// projected onto its other two components, into triangles that wind in the
+/// *same* direction the loop itself was given in — i.e. `loop_points[i] ->
+/// loop_points[i + 1]` appears in that order in whichever output triangle
+/// holds that edge. This is what lets a caller choose the cap's winding
+/// (and therefore its outward normal) by choosing the loop's direction,
+/// rather than this function silently normalizing to one absolute winding.
+pub fn triangulate_cap(loop_points: &[Vector<f32>], axis: usize) -> Vec<Triangle> {
+    let mut triangles = Vec::new();
+    if loop_points.len() < 3 {
+        return triangles;
+    }
+    let (u, v) = projection_axes(axis);
+
+    let mut indices: Vec<usize> = (0..loop_points.len()).collect();
+    // The convexity/ear tests below assume a CCW traversal in (u, v). If the
+    // caller's loop runs CW there, clip a reversed working copy instead and
+    // flip each emitted triangle's winding back at the end, so the output
+    // still follows the caller's own direction rather than this function's
+    // internal working order.
+    let flip_output = signed_area(loop_points, &indices, u, v) < 0.0;
+    if flip_output {
+        indices.reverse();
+    }
+
+    while indices.len() > 3 {
+        let mut ear_found = false;
+        for i in 0..indices.len() {
+            let prev = indices[(i + indices.len() - 1) % indices.len()];
+            let curr = indices[i];
+            let next = indices[(i + 1) % indices.len()];
+
+            if is_ear(loop_points, &indices, prev, curr, next, u, v) {
+                triangles.push(make_triangle(loop_points, prev, curr, next, flip_output));
+                indices.remove(i);
+                ear_found = true;
+                break;
+            }
+        }
+        if !ear_found {
+            // Degenerate or self-intersecting loop; stop rather than spin forever.
+            break;
+        }
+    }
+
+    if indices.len() == 3 {
+        triangles.push(make_triangle(loop_points, indices[0], indices[1], indices[2], flip_output));
+    }
+
+    triangles
+}
+
+/// Build a cap triangle from three loop points, undoing the internal
+/// CCW-normalization (`flip_output`) so the result still winds the way the
+/// caller's loop did, and deriving its normal from that actual winding
+/// rather than trusting a separately-passed value to agree with it.
+fn make_triangle(loop_points: &[Vector<f32>], a: usize, b: usize, c: usize, flip_output: bool) -> Triangle {
+    let vertices = if flip_output {
+        [loop_points[a], loop_points[c], loop_points[b]]
+    } else {
+        [loop_points[a], loop_points[b], loop_points[c]]
+    };
+    Triangle {
+        normal: geometry::triangle_normal(vertices[0], vertices[1], vertices[2]),
+        vertices,
+    }
+}
+
+/// The two axes spanning the plane perpendicular to `axis`, in a fixed
+/// cyclic order so winding stays consistent regardless of cut axis.
+fn projection_axes(axis: usize) -> (usize, usize) {
+    ((axis + 1) % 3, (axis + 2) % 3)
+}
+
+fn signed_area(points: &[Vector<f32>], indices: &[usize], u: usize, v: usize) -> f32 {
+    let mut area = 0.0;
+    for i in 0..indices.len() {
+        let a = points[indices[i]];
+        let b = points[indices[(i + 1) % indices.len()]];
+        area += a[u] * b[v] - b[u] * a[v];
+    }
+    area
+}
+
+fn is_convex(points: &[Vector<f32>], prev: usize, curr: usize, next: usize, u: usize, v: usize) -> bool {
+    let a = points[prev];
+    let b = points[curr];
+    let c = points[next];
+    let cross = (b[u] - a[u]) * (c[v] - a[v]) - (b[v] - a[v]) * (c[u] - a[u]);
+    cross >= 0.0
+}
+
+#[allow(clippy::too_many_arguments)]
+fn is_ear(points: &[Vector<f32>], indices: &[usize], prev: usize, curr: usize, next: usize, u: usize, v: usize) -> bool {
+    if !is_convex(points, prev, curr, next, u, v) {
+        return false;
+    }
+    let (a, b, c) = (points[prev], points[curr], points[next]);
+    indices
+        .iter()
+        .filter(|&&idx| idx != prev && idx != curr && idx != next)
+        .all(|&idx| !point_in_triangle(points[idx], a, b, c, u, v))
+}
+
+fn point_in_triangle(p: Vector<f32>, a: Vector<f32>, b: Vector<f32>, c: Vector<f32>, u: usize, v: usize) -> bool {
+    let d1 = sign(p, a, b, u, v);
+    let d2 = sign(p, b, c, u, v);
+    let d3 = sign(p, c, a, u, v);
+    let has_neg = d1 < 0.0 || d2 < 0.0 || d3 < 0.0;
+    let has_pos = d1 > 0.0 || d2 > 0.0 || d3 > 0.0;
+    !(has_neg && has_pos)
+}
+
+fn sign(p: Vector<f32>, a: Vector<f32>, b: Vector<f32>, u: usize, v: usize) -> f32 {
+    (p[u] - b[u]) * (a[v] - b[v]) - (a[u] - b[u]) * (p[v] - b[v])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A unit square in the z=0 plane, listed counter-clockwise as seen
+    /// looking down the +z axis.
+    fn square_loop() -> Vec<Vector<f32>> {
+        vec![
+            Vector::new([0.0, 0.0, 0.0]),
+            Vector::new([1.0, 0.0, 0.0]),
+            Vector::new([1.0, 1.0, 0.0]),
+            Vector::new([0.0, 1.0, 0.0]),
+        ]
+    }
+
+    /// All directed edges actually traced by a set of triangles: for each
+    /// triangle, the three `(vertex[i], vertex[i+1])` pairs in winding order.
+    fn directed_edges(triangles: &[Triangle]) -> Vec<(Vector<f32>, Vector<f32>)> {
+        triangles
+            .iter()
+            .flat_map(|t| {
+                (0..3).map(|i| (t.vertices[i], t.vertices[(i + 1) % 3]))
+            })
+            .collect()
+    }
+
+    fn approx_eq(a: Vector<f32>, b: Vector<f32>) -> bool {
+        (a[0] - b[0]).abs() < 1e-4 && (a[1] - b[1]).abs() < 1e-4 && (a[2] - b[2]).abs() < 1e-4
+    }
+
+    #[test]
+    fn triangulate_cap_quad_follows_the_loops_own_direction() {
+        let loop_points = square_loop();
+        let triangles = triangulate_cap(&loop_points, 2);
+
+        assert_eq!(triangles.len(), 2);
+        let edges = directed_edges(&triangles);
+        // Every boundary edge of the input loop, in the loop's own
+        // direction, must show up as a directed edge of some triangle.
+        for i in 0..loop_points.len() {
+            let a = loop_points[i];
+            let b = loop_points[(i + 1) % loop_points.len()];
+            assert!(
+                edges.iter().any(|&(ea, eb)| approx_eq(ea, a) && approx_eq(eb, b)),
+                "missing boundary edge in the loop's own direction"
+            );
+        }
+    }
+
+    #[test]
+    fn triangulate_cap_reversed_loop_follows_the_reversed_direction() {
+        let mut reversed = square_loop();
+        reversed.reverse();
+
+        let triangles = triangulate_cap(&reversed, 2);
+
+        assert_eq!(triangles.len(), 2);
+        let edges = directed_edges(&triangles);
+        for i in 0..reversed.len() {
+            let a = reversed[i];
+            let b = reversed[(i + 1) % reversed.len()];
+            assert!(
+                edges.iter().any(|&(ea, eb)| approx_eq(ea, a) && approx_eq(eb, b)),
+                "missing boundary edge in the reversed loop's own direction"
+            );
+        }
+    }
+
+    #[test]
+    fn triangulate_cap_triangle_loop_yields_one_triangle_in_given_order() {
+        let loop_points = vec![
+            Vector::new([0.0, 0.0, 0.0]),
+            Vector::new([1.0, 0.0, 0.0]),
+            Vector::new([0.0, 1.0, 0.0]),
+        ];
+
+        let triangles = triangulate_cap(&loop_points, 2);
+
+        assert_eq!(triangles.len(), 1);
+        assert_eq!(triangles[0].vertices, [loop_points[0], loop_points[1], loop_points[2]]);
+    }
+
+    #[test]
+    fn stitch_loops_joins_segments_given_in_non_adjacent_order() {
+        let square = square_loop();
+        let edges: Vec<Segment> = [(0, 1), (1, 2), (2, 3), (3, 0)]
+            .iter()
+            .map(|&(a, b)| [square[a], square[b]])
+            .collect();
+
+        // Insert the four edges out of chain order so `stitch_loops` has to
+        // search rather than happening to find them already adjacent.
+        let scrambled = vec![edges[2], edges[0], edges[3], edges[1]];
+
+        let loops = stitch_loops(scrambled);
+
+        assert_eq!(loops.len(), 1);
+        assert_eq!(loops[0].len(), 4);
+    }
+
+    #[test]
+    fn stitch_loops_preserves_the_segments_own_direction() {
+        let square = square_loop();
+        let edges: Vec<Segment> = [(0, 1), (1, 2), (2, 3), (3, 0)]
+            .iter()
+            .map(|&(a, b)| [square[a], square[b]])
+            .collect();
+
+        let loops = stitch_loops(edges);
+
+        assert_eq!(loops.len(), 1);
+        // Rotate the result to start at the same point as `square` so the
+        // directed order is directly comparable.
+        let start = loops[0].iter().position(|&p| approx_eq(p, square[0])).unwrap();
+        let rotated: Vec<_> = loops[0].iter().cycle().skip(start).take(4).copied().collect();
+        for (got, want) in rotated.iter().zip(square.iter()) {
+            assert!(approx_eq(*got, *want), "stitch_loops reordered or reversed the segments");
+        }
+    }
+
+    #[test]
+    fn stitch_loops_drops_an_open_chain() {
+        let square = square_loop();
+        // Only three of the four edges: the chain never closes back on
+        // itself, so there's nothing sane to cap.
+        let open_chain: Vec<Segment> = [(0, 1), (1, 2), (2, 3)]
+            .iter()
+            .map(|&(a, b)| [square[a], square[b]])
+            .collect();
+
+        let loops = stitch_loops(open_chain);
+
+        assert!(loops.is_empty());
+    }
+}
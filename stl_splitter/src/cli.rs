@@ -0,0 +1,66 @@
+use clap::{Parser, ValueEnum};
+use std::path::PathBuf;
+
+/// Split or slice an STL model along one axis.
+#[derive(Parser, Debug)]
+#[command(author, version, about)]
+pub struct Args {
+    /// STL file to process; if omitted, scans models/input for candidates.
+    pub input: Option<PathBuf>,
+
+    /// Axis to cut along.
+    #[arg(long, value_enum, default_value_t = Axis::Z)]
+    pub axis: Axis,
+
+    /// Absolute coordinate along `axis` to cut at (overrides --fraction and --slices).
+    #[arg(long)]
+    pub at: Option<f32>,
+
+    /// Fraction (0.0-1.0) of the model's span along `axis` to cut at (overrides --slices).
+    #[arg(long)]
+    pub fraction: Option<f32>,
+
+    /// Cut the model into this many equally sized pieces along `axis`.
+    #[arg(long, default_value_t = 2)]
+    pub slices: usize,
+
+    /// Seal each cut cross-section with a flat cap instead of leaving it open.
+    #[arg(long)]
+    pub capped: bool,
+
+    /// Glob pattern (e.g. "models/input/**/*.stl") to batch-process many
+    /// files in parallel instead of a single `input`.
+    #[arg(long)]
+    pub glob: Option<String>,
+
+    /// In glob batch mode, keep processing the remaining files after one
+    /// fails instead of aborting the whole batch.
+    #[arg(long)]
+    pub keep_going: bool,
+}
+
+#[derive(Copy, Clone, Debug, ValueEnum)]
+pub enum Axis {
+    X,
+    Y,
+    Z,
+}
+
+impl Axis {
+    /// The component index (0 = x, 1 = y, 2 = z) this axis selects.
+    pub fn index(self) -> usize {
+        match self {
+            Axis::X => 0,
+            Axis::Y => 1,
+            Axis::Z => 2,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Axis::X => "X",
+            Axis::Y => "Y",
+            Axis::Z => "Z",
+        }
+    }
+}
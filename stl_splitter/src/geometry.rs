@@ -0,0 +1,43 @@
+use stl_io::Vector;
+
+/// Tolerance used when comparing a vertex's signed distance to the split
+/// plane, and when deciding whether an edge-plane intersection falls so
+/// close to an existing vertex that it would produce a zero-area sliver.
+pub const EPSILON: f32 = 1e-5;
+
+pub fn sub(a: Vector<f32>, b: Vector<f32>) -> [f32; 3] {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+pub fn cross(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+pub fn normalize(v: [f32; 3]) -> [f32; 3] {
+    let len = (v[0] * v[0] + v[1] * v[1] + v[2] * v[2]).sqrt();
+    if len < EPSILON {
+        [0.0, 0.0, 0.0]
+    } else {
+        [v[0] / len, v[1] / len, v[2] / len]
+    }
+}
+
+/// Linear interpolation between two vertices: `a + t * (b - a)`.
+pub fn lerp(a: Vector<f32>, b: Vector<f32>, t: f32) -> Vector<f32> {
+    Vector::new([
+        a[0] + t * (b[0] - a[0]),
+        a[1] + t * (b[1] - a[1]),
+        a[2] + t * (b[2] - a[2]),
+    ])
+}
+
+/// The facet normal of the triangle `(v0, v1, v2)`: `normalize((v1 - v0) x
+/// (v2 - v0))`. Degenerate, zero-area triangles fall back to the zero
+/// vector rather than producing NaNs.
+pub fn triangle_normal(v0: Vector<f32>, v1: Vector<f32>, v2: Vector<f32>) -> Vector<f32> {
+    Vector::new(normalize(cross(sub(v1, v0), sub(v2, v0))))
+}
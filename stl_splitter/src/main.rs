@@ -1,180 +1,175 @@
-use std::fs;
-use std::path::{Path, PathBuf};
-use std::io::{self, BufReader, Write};
-use stl_io::{Triangle, Vector};
-use anyhow::{Result, Context};
-
-struct Mesh {
-    triangles: Vec<Triangle>,
-}
-
-impl Mesh {
-    fn load(path: &Path) -> Result<Self> {
-        let file = fs::File::open(path)?;
-        let mut reader = BufReader::new(file);
-        let mesh = stl_io::read_stl(&mut reader)?;
-        
-        // Ensure we have complete triangles (3 vertices per triangle)
-        if mesh.vertices.len() % 3 != 0 {
-            return Err(anyhow::anyhow!("Invalid STL file: vertex count not divisible by 3"));
-        }
-
-        // Convert vertices to triangles
-        let triangles = mesh.vertices
-            .chunks_exact(3)  // Use chunks_exact to ensure we get complete triangles
-            .map(|vertices| {
-                Triangle {
-                    normal: Vector([0.0, 0.0, 1.0]),
-                    vertices: [
-                        vertices[0],
-                        vertices[1],
-                        vertices[2]
-                    ]
-                }
-            })
-            .collect::<Vec<_>>();
-
-        if triangles.is_empty() {
-            return Err(anyhow::anyhow!("No triangles found in STL file"));
-        }
-
-        Ok(Mesh { triangles })
-    }
-
-    fn get_dimensions(&self) -> ([f32; 3], [f32; 3]) {
-        let first_vertex = self.triangles[0].vertices[0];
-        let mut min_array = [first_vertex[0], first_vertex[1], first_vertex[2]];
-        let mut max_array = [first_vertex[0], first_vertex[1], first_vertex[2]];
-
-        for triangle in &self.triangles {
-            for vertex in &triangle.vertices {
-                for i in 0..3 {
-                    min_array[i] = min_array[i].min(vertex[i]);
-                    max_array[i] = max_array[i].max(vertex[i]);
-                }
-            }
-        }
-        (min_array, max_array)
-    }
-
-    fn split(&self, z_height: f32) -> (Vec<Triangle>, Vec<Triangle>) {
-        let mut upper = Vec::new();
-        let mut lower = Vec::new();
+mod batch;
+mod cap;
+mod cli;
+mod geometry;
+mod mesh;
+mod weld;
 
-        for triangle in &self.triangles {
-            if triangle.vertices.iter().all(|v| v[2] >= z_height) {
-                upper.push(*triangle);
-            } else if triangle.vertices.iter().all(|v| v[2] <= z_height) {
-                lower.push(*triangle);
-            }
-        }
+use std::fs;
+use std::io::{self, Write};
+use std::path::PathBuf;
 
-        (upper, lower)
-    }
+use anyhow::{Context, Result};
+use clap::Parser;
+use stl_io::Triangle;
 
-    fn save(triangles: &[Triangle], path: &Path) -> Result<()> {
-        let file = fs::File::create(path)?;
-        stl_io::write_stl(&mut io::BufWriter::new(file), triangles.iter())?;
-        Ok(())
-    }
-}
+use cli::Args;
+use mesh::Mesh;
 
 fn main() -> Result<()> {
+    let args = Args::parse();
+
     // Create directories if they don't exist
     let input_dir = PathBuf::from("models/input");
     let output_dir = PathBuf::from("models/output");
     fs::create_dir_all(&input_dir)?;
     fs::create_dir_all(&output_dir)?;
 
-    // Get input file from command line arguments or scan directory
-    let input_path = std::env::args()
-        .nth(1)
-        .map(PathBuf::from)
-        .or_else(|| -> Option<PathBuf> {
-            // If no argument provided, scan input directory
-            let entries = match fs::read_dir(&input_dir) {
-                Ok(entries) => entries,
-                Err(e) => {
-                    println!("Failed to read input directory: {}", e);
-                    return None;
-                }
-            };
-            
-            let stl_files: Vec<_> = entries
-                .filter_map(|entry| {
-                    let entry = entry.ok()?;
-                    let path = entry.path();
-                    if path.extension()?.to_str()? == "stl" {
-                        Some(path)
-                    } else {
-                        None
-                    }
-                })
-                .collect();
-
-            if stl_files.is_empty() {
-                println!("No STL files found in input directory");
-                return None;
-            }
-
-            // Display available files
-            println!("Available STL files:");
-            for (i, path) in stl_files.iter().enumerate() {
-                println!("{}. {}", i + 1, path.file_name().unwrap().to_string_lossy());
-            }
-
-            // Get user input
-            print!("Select file number to process: ");
-            if io::stdout().flush().is_err() {
-                return None;
-            }
-
-            let mut input = String::new();
-            if io::stdin().read_line(&mut input).is_err() {
-                return None;
-            }
-
-            let file_idx = match input.trim().parse::<usize>() {
-                Ok(idx) => idx - 1,
-                Err(_) => return None,
-            };
-
-            if file_idx >= stl_files.len() {
-                println!("Invalid file number");
-                return None;
-            }
+    if let Some(pattern) = &args.glob {
+        return batch::run(pattern, &args, &output_dir);
+    }
 
-            Some(stl_files[file_idx].clone())
-        })
+    let input_path = args
+        .input
+        .clone()
+        .or_else(|| prompt_for_input(&input_dir))
         .context("No input file specified")?;
 
     println!("Loading {}...", input_path.display());
-    
+
     // Load and process mesh
     let mesh = Mesh::load(&input_path)?;
     let (min, max) = mesh.get_dimensions();
-    
+
     println!("Model dimensions:");
     println!("X: {:.2} to {:.2}", min[0], max[0]);
     println!("Y: {:.2} to {:.2}", min[1], max[1]);
     println!("Z: {:.2} to {:.2}", min[2], max[2]);
 
-    let z_split = (max[2] + min[2]) / 2.0;
-    println!("Splitting at Z = {:.2}", z_split);
+    let axis = args.axis.index();
+    let planes = cut_planes(&args, min[axis], max[axis]);
+
+    println!(
+        "Cutting along {} at: {}",
+        args.axis.label(),
+        planes
+            .iter()
+            .map(|p| format!("{:.2}", p))
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+
+    let pieces = slice(&mesh, axis, &planes, args.capped);
+
+    // Save each piece, named by slice index
+    let base_name = input_path.file_stem().unwrap().to_string_lossy();
+    for (i, piece) in pieces.iter().enumerate() {
+        let piece_path = output_dir.join(format!("{}_{:03}.stl", base_name, i));
+        let stats = Mesh::save(piece, &piece_path)?;
+        println!(
+            "Saved {} ({} triangles, {} unique vertices, content hash {:032x})",
+            piece_path.file_name().unwrap().to_string_lossy(),
+            stats.triangle_count,
+            stats.vertex_count,
+            stats.content_hash,
+        );
+    }
+
+    println!("Split complete!");
 
-    let (upper, lower) = mesh.split(z_split);
+    Ok(())
+}
 
-    // Save split parts
-    let base_name = input_path.file_stem().unwrap();
-    let upper_path = output_dir.join(format!("{}_upper.stl", base_name.to_string_lossy()));
-    let lower_path = output_dir.join(format!("{}_lower.stl", base_name.to_string_lossy()));
+/// The coordinates, in ascending order, at which to cut along the chosen
+/// axis: a single explicit `--at`/`--fraction` location, or `--slices - 1`
+/// planes evenly spaced across the model's span.
+pub(crate) fn cut_planes(args: &Args, min: f32, max: f32) -> Vec<f32> {
+    if let Some(at) = args.at {
+        return vec![at];
+    }
+    if let Some(fraction) = args.fraction {
+        return vec![min + fraction.clamp(0.0, 1.0) * (max - min)];
+    }
+    let slices = args.slices.max(1);
+    (1..slices)
+        .map(|i| min + (max - min) * (i as f32 / slices as f32))
+        .collect()
+}
 
-    Mesh::save(&upper, &upper_path)?;
-    Mesh::save(&lower, &lower_path)?;
+/// Cut `mesh` along `axis` at each plane in turn, peeling the lower piece
+/// off at every cut and returning all resulting pieces in ascending order.
+pub(crate) fn slice(mesh: &Mesh, axis: usize, planes: &[f32], capped: bool) -> Vec<Vec<Triangle>> {
+    let mut pieces = Vec::with_capacity(planes.len() + 1);
+    let mut remainder: Vec<Triangle> = mesh.triangles().to_vec();
+
+    for &plane in planes {
+        let current = Mesh::from(remainder);
+        let (upper, lower) = if capped {
+            current.split_capped(axis, plane)
+        } else {
+            current.split(axis, plane)
+        };
+        pieces.push(lower);
+        remainder = upper;
+    }
+    pieces.push(remainder);
 
-    println!("Split complete!");
-    println!("Upper part saved as: {}", upper_path.file_name().unwrap().to_string_lossy());
-    println!("Lower part saved as: {}", lower_path.file_name().unwrap().to_string_lossy());
+    pieces
+}
 
-    Ok(())
+/// Scan `input_dir` for STL files and let the user pick one interactively.
+fn prompt_for_input(input_dir: &std::path::Path) -> Option<PathBuf> {
+    let entries = match fs::read_dir(input_dir) {
+        Ok(entries) => entries,
+        Err(e) => {
+            println!("Failed to read input directory: {}", e);
+            return None;
+        }
+    };
+
+    let stl_files: Vec<_> = entries
+        .filter_map(|entry| {
+            let entry = entry.ok()?;
+            let path = entry.path();
+            if path.extension()?.to_str()? == "stl" {
+                Some(path)
+            } else {
+                None
+            }
+        })
+        .collect();
+
+    if stl_files.is_empty() {
+        println!("No STL files found in input directory");
+        return None;
+    }
+
+    // Display available files
+    println!("Available STL files:");
+    for (i, path) in stl_files.iter().enumerate() {
+        println!("{}. {}", i + 1, path.file_name().unwrap().to_string_lossy());
+    }
+
+    // Get user input
+    print!("Select file number to process: ");
+    if io::stdout().flush().is_err() {
+        return None;
+    }
+
+    let mut input = String::new();
+    if io::stdin().read_line(&mut input).is_err() {
+        return None;
+    }
+
+    let file_idx = match input.trim().parse::<usize>() {
+        Ok(idx) => idx - 1,
+        Err(_) => return None,
+    };
+
+    if file_idx >= stl_files.len() {
+        println!("Invalid file number");
+        return None;
+    }
+
+    Some(stl_files[file_idx].clone())
 }
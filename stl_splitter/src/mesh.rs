@@ -0,0 +1,479 @@
+use std::fs;
+use std::io::{self, BufReader};
+use std::path::Path;
+
+use anyhow::Result;
+use stl_io::{Triangle, Vector};
+
+use crate::cap::{self, Segment};
+use crate::geometry::{self, lerp, EPSILON};
+use crate::weld;
+
+/// Statistics reported after [`Mesh::save`] welds shared vertices together,
+/// so callers can see how much duplication the flat triangle soup had and
+/// recognize geometrically identical output via `content_hash`.
+pub struct SaveStats {
+    pub triangle_count: usize,
+    pub vertex_count: usize,
+    pub content_hash: u128,
+}
+
+pub struct Mesh {
+    triangles: Vec<Triangle>,
+}
+
+impl Mesh {
+    pub fn load(path: &Path) -> Result<Self> {
+        let file = fs::File::open(path)?;
+        let mut reader = BufReader::new(file);
+        let mesh = stl_io::read_stl(&mut reader)?;
+
+        // `mesh.vertices` is stl_io's deduplicated vertex table; the real
+        // faces are `mesh.faces`, each holding indices into that table, so
+        // triangles have to be rebuilt from the index triples rather than by
+        // chunking the vertex table itself.
+        let triangles = mesh.faces
+            .iter()
+            .map(|face| {
+                Triangle {
+                    normal: Vector::new([0.0, 0.0, 0.0]),
+                    vertices: [
+                        mesh.vertices[face.vertices[0]],
+                        mesh.vertices[face.vertices[1]],
+                        mesh.vertices[face.vertices[2]],
+                    ]
+                }
+            })
+            .collect::<Vec<_>>();
+
+        if triangles.is_empty() {
+            return Err(anyhow::anyhow!("No triangles found in STL file"));
+        }
+
+        let mut mesh = Mesh { triangles };
+        mesh.recompute_normals();
+        Ok(mesh)
+    }
+
+    pub fn triangles(&self) -> &[Triangle] {
+        &self.triangles
+    }
+
+    /// Recompute every triangle's facet normal from its geometry instead of
+    /// trusting whatever was stored (or left as a placeholder), so slicers
+    /// and viewers that rely on facet normals for inside/outside tests see
+    /// correct orientation.
+    pub fn recompute_normals(&mut self) {
+        for triangle in self.triangles.iter_mut() {
+            triangle.normal = geometry::triangle_normal(
+                triangle.vertices[0],
+                triangle.vertices[1],
+                triangle.vertices[2],
+            );
+        }
+    }
+
+    pub fn get_dimensions(&self) -> ([f32; 3], [f32; 3]) {
+        let first_vertex = self.triangles[0].vertices[0];
+        let mut min_array = [first_vertex[0], first_vertex[1], first_vertex[2]];
+        let mut max_array = [first_vertex[0], first_vertex[1], first_vertex[2]];
+
+        for triangle in &self.triangles {
+            for vertex in &triangle.vertices {
+                for i in 0..3 {
+                    min_array[i] = min_array[i].min(vertex[i]);
+                    max_array[i] = max_array[i].max(vertex[i]);
+                }
+            }
+        }
+        (min_array, max_array)
+    }
+
+    /// Split the mesh at `coord` along `axis` (0 = x, 1 = y, 2 = z), clipping
+    /// any triangle that straddles the plane so both halves keep the full
+    /// cut surface instead of leaving ragged holes where facets used to
+    /// cross it.
+    pub fn split(&self, axis: usize, coord: f32) -> (Vec<Triangle>, Vec<Triangle>) {
+        let mut upper = Vec::new();
+        let mut lower = Vec::new();
+
+        for triangle in &self.triangles {
+            clip_triangle(triangle, axis, coord, &mut upper, &mut lower, None);
+        }
+
+        (upper, lower)
+    }
+
+    /// Split the mesh like [`Mesh::split`], then seal the open cut
+    /// cross-section on both halves with a flat, manifold cap so each half
+    /// is printable on its own.
+    pub fn split_capped(&self, axis: usize, coord: f32) -> (Vec<Triangle>, Vec<Triangle>) {
+        let mut upper = Vec::new();
+        let mut lower = Vec::new();
+        let mut segments: Vec<Segment> = Vec::new();
+
+        for triangle in &self.triangles {
+            clip_triangle(triangle, axis, coord, &mut upper, &mut lower, Some(&mut segments));
+        }
+
+        for loop_points in cap::stitch_loops(segments) {
+            // Each stitched loop runs in the direction consistent with the
+            // *upper* wall's own cut-boundary winding at every point along
+            // it (see `clip_triangle`); the lower wall's boundary runs the
+            // opposite way at every point. For a seam edge to be manifold
+            // (shared once forward, once backward, between a wall face and
+            // its cap), each cap's boundary must run opposite to that half's
+            // own wall winding — so the lower cap uses the loop as stitched,
+            // and the upper cap uses it reversed.
+            let lower_cap = cap::triangulate_cap(&loop_points, axis);
+            let mut reversed_loop = loop_points.clone();
+            reversed_loop.reverse();
+            let upper_cap = cap::triangulate_cap(&reversed_loop, axis);
+            upper.extend(upper_cap);
+            lower.extend(lower_cap);
+        }
+
+        (upper, lower)
+    }
+
+    /// Write `triangles` to `path` as an STL file. The STL format has no
+    /// concept of shared vertices, so the file itself is still a flat
+    /// triangle soup; welding happens only to report how much duplication
+    /// there was and to fingerprint the geometry.
+    pub fn save(triangles: &[Triangle], path: &Path) -> Result<SaveStats> {
+        let welded = weld::weld(triangles, weld::DEFAULT_TOLERANCE);
+
+        let file = fs::File::create(path)?;
+        stl_io::write_stl(&mut io::BufWriter::new(file), triangles.iter())?;
+
+        Ok(SaveStats {
+            triangle_count: triangles.len(),
+            vertex_count: welded.vertex_count(),
+            content_hash: welded.content_hash(weld::DEFAULT_TOLERANCE),
+        })
+    }
+}
+
+impl From<Vec<Triangle>> for Mesh {
+    fn from(triangles: Vec<Triangle>) -> Self {
+        Mesh { triangles }
+    }
+}
+
+/// Classify and, if necessary, clip a single triangle against the plane
+/// `axis = coord`, pushing the resulting triangle(s) into `upper` and/or
+/// `lower`. Vertices within `EPSILON` of the plane are treated as lying on
+/// it, so they contribute to both sides and no gap is left along the seam.
+///
+/// When `segments` is given and the triangle genuinely straddles the plane,
+/// the edge where it crosses is recorded so [`Mesh::split_capped`] can later
+/// stitch these per-triangle segments into closed loops and cap them.
+fn clip_triangle(
+    triangle: &Triangle,
+    axis: usize,
+    coord: f32,
+    upper: &mut Vec<Triangle>,
+    lower: &mut Vec<Triangle>,
+    segments: Option<&mut Vec<Segment>>,
+) {
+    let verts = triangle.vertices;
+    let mut dist = [
+        verts[0][axis] - coord,
+        verts[1][axis] - coord,
+        verts[2][axis] - coord,
+    ];
+    for d in dist.iter_mut() {
+        if d.abs() < EPSILON {
+            *d = 0.0;
+        }
+    }
+
+    let mut upper_poly = Vec::with_capacity(4);
+    let mut lower_poly = Vec::with_capacity(4);
+    let mut crossings = Vec::with_capacity(2);
+    // For each entry in `crossings`, the index it landed at in `upper_poly`
+    // when it was pushed there — needed below to work out which direction
+    // along the cut the two crossing points actually run.
+    let mut upper_positions = Vec::with_capacity(2);
+
+    for i in 0..3 {
+        let j = (i + 1) % 3;
+        let (vi, vj) = (verts[i], verts[j]);
+        let (di, dj) = (dist[i], dist[j]);
+
+        if di >= 0.0 {
+            upper_poly.push(vi);
+        }
+        if di <= 0.0 {
+            lower_poly.push(vi);
+        }
+        // A vertex lying exactly on the plane is itself an endpoint of the
+        // cut cross-section, not just the edges that cross through it.
+        if di == 0.0 {
+            crossings.push(vi);
+            upper_positions.push(upper_poly.len() - 1);
+        }
+
+        if (di > 0.0 && dj < 0.0) || (di < 0.0 && dj > 0.0) {
+            let t = di / (di - dj);
+            // Skip intersections that land on top of an existing vertex;
+            // they'd only add a degenerate, zero-area sliver.
+            if t > EPSILON && t < 1.0 - EPSILON {
+                let p = lerp(vi, vj, t);
+                upper_poly.push(p);
+                lower_poly.push(p);
+                crossings.push(p);
+                upper_positions.push(upper_poly.len() - 1);
+            }
+        }
+    }
+
+    if let Some(segments) = segments {
+        if crossings.len() == 2 {
+            // `crossings` lists the two cut points in this triangle's own
+            // traversal order, but that isn't always the direction the
+            // *upper* polygon's boundary actually runs along the cut: the
+            // two points end up adjacent in `upper_poly` (direction matches
+            // `crossings` order) when upper keeps the lone vertex, but only
+            // adjacent by wrapping around (direction is reversed) when upper
+            // keeps the other two. Store whichever direction is the real
+            // one, so every triangle's segment threads into one globally
+            // consistent loop direction in `stitch_loops`.
+            let (a, b) = (upper_positions[0], upper_positions[1]);
+            let directed = if b == a + 1 {
+                [crossings[0], crossings[1]]
+            } else {
+                [crossings[1], crossings[0]]
+            };
+            segments.push(directed);
+        }
+    }
+
+    fan_triangulate(&upper_poly, triangle.normal, upper);
+    fan_triangulate(&lower_poly, triangle.normal, lower);
+}
+
+/// Fan-triangulate a convex polygon (3 or 4 coplanar-ish points, in winding
+/// order) produced by clipping, emitting the resulting triangles into `out`.
+fn fan_triangulate(poly: &[Vector<f32>], normal: Vector<f32>, out: &mut Vec<Triangle>) {
+    if poly.len() < 3 {
+        return;
+    }
+    for i in 1..poly.len() - 1 {
+        out.push(Triangle {
+            normal,
+            vertices: [poly[0], poly[i], poly[i + 1]],
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn triangle(a: [f32; 3], b: [f32; 3], c: [f32; 3]) -> Triangle {
+        Triangle {
+            normal: Vector::new([0.0, 0.0, 1.0]),
+            vertices: [Vector::new(a), Vector::new(b), Vector::new(c)],
+        }
+    }
+
+    /// A unit cube as 12 triangles (2 per face) sharing its 8 corners, the
+    /// way a real-world STL exporter would emit one.
+    fn cube_triangles() -> Vec<Triangle> {
+        let c = |x: f32, y: f32, z: f32| Vector::new([x, y, z]);
+        let quad = |a, b, c2, d, n: [f32; 3]| {
+            vec![
+                Triangle { normal: Vector::new(n), vertices: [a, b, c2] },
+                Triangle { normal: Vector::new(n), vertices: [a, c2, d] },
+            ]
+        };
+
+        let (v000, v100, v110, v010) = (c(0.0, 0.0, 0.0), c(1.0, 0.0, 0.0), c(1.0, 1.0, 0.0), c(0.0, 1.0, 0.0));
+        let (v001, v101, v111, v011) = (c(0.0, 0.0, 1.0), c(1.0, 0.0, 1.0), c(1.0, 1.0, 1.0), c(0.0, 1.0, 1.0));
+
+        let mut triangles = Vec::new();
+        triangles.extend(quad(v000, v100, v110, v010, [0.0, 0.0, -1.0])); // bottom
+        triangles.extend(quad(v001, v011, v111, v101, [0.0, 0.0, 1.0])); // top
+        triangles.extend(quad(v000, v010, v011, v001, [-1.0, 0.0, 0.0])); // left
+        triangles.extend(quad(v100, v101, v111, v110, [1.0, 0.0, 0.0])); // right
+        triangles.extend(quad(v000, v001, v101, v100, [0.0, -1.0, 0.0])); // front
+        triangles.extend(quad(v010, v110, v111, v011, [0.0, 1.0, 0.0])); // back
+        triangles
+    }
+
+    /// A vertex position quantized to `EPSILON`, so float noise from
+    /// clipping/capping doesn't stop two geometrically identical points from
+    /// being recognized as the same edge endpoint.
+    type QuantizedVertex = (i64, i64, i64);
+
+    /// Quantized directed edges of `triangles`, one per winding-order pair
+    /// `(vertices[i], vertices[i + 1])`.
+    fn directed_edges(triangles: &[Triangle]) -> Vec<(QuantizedVertex, QuantizedVertex)> {
+        let key = |v: Vector<f32>| {
+            (
+                (v[0] / EPSILON).round() as i64,
+                (v[1] / EPSILON).round() as i64,
+                (v[2] / EPSILON).round() as i64,
+            )
+        };
+        triangles
+            .iter()
+            .flat_map(|t| (0..3).map(|i| (key(t.vertices[i]), key(t.vertices[(i + 1) % 3]))))
+            .collect()
+    }
+
+    /// A closed, consistently-wound mesh is manifold exactly when every
+    /// directed edge has exactly one matching reverse directed edge
+    /// elsewhere in the mesh — i.e. each edge is shared by two faces that
+    /// traverse it in opposite directions. A cap that winds the *same* way
+    /// as the wall it seals leaves that seam edge unpaired (or duplicated in
+    /// the same direction), which this catches.
+    fn assert_edges_pair_up(triangles: &[Triangle]) {
+        let edges = directed_edges(triangles);
+        for &(a, b) in &edges {
+            let forward = edges.iter().filter(|&&(x, y)| (x, y) == (a, b)).count();
+            let backward = edges.iter().filter(|&&(x, y)| (x, y) == (b, a)).count();
+            assert_eq!(forward, 1, "directed edge {:?} -> {:?} appears {} times, expected 1", a, b, forward);
+            assert_eq!(backward, 1, "reverse edge {:?} -> {:?} appears {} times, expected 1", b, a, backward);
+        }
+    }
+
+    #[test]
+    fn cube_triangles_fixture_is_itself_manifold() {
+        assert_edges_pair_up(&cube_triangles());
+    }
+
+    #[test]
+    fn split_capped_halves_are_each_manifold() {
+        let mesh = Mesh::from(cube_triangles());
+        let (upper, lower) = mesh.split_capped(2, 0.5);
+
+        assert_edges_pair_up(&upper);
+        assert_edges_pair_up(&lower);
+    }
+
+    #[test]
+    fn load_reconstructs_triangles_from_indexed_faces_not_raw_vertex_chunks() {
+        // stl_io dedupes the cube's 36 vertex slots down to its 8 distinct
+        // corners, which isn't a multiple of 3 — the old code that chunked
+        // `mesh.vertices` directly rejected this file outright, even though
+        // it's a perfectly valid STL.
+        let expected = cube_triangles();
+        let mut path = std::env::temp_dir();
+        path.push(format!("stl_splitter_test_cube_{}.stl", std::process::id()));
+        {
+            let file = fs::File::create(&path).unwrap();
+            stl_io::write_stl(&mut io::BufWriter::new(file), expected.iter()).unwrap();
+        }
+
+        let mesh = Mesh::load(&path).unwrap();
+        fs::remove_file(&path).ok();
+
+        assert_eq!(mesh.triangles().len(), expected.len());
+
+        let (min, max) = mesh.get_dimensions();
+        assert_eq!(min, [0.0, 0.0, 0.0]);
+        assert_eq!(max, [1.0, 1.0, 1.0]);
+    }
+
+    #[test]
+    fn clip_triangle_entirely_above_plane_stays_whole() {
+        let t = triangle([0.0, 0.0, 1.0], [1.0, 0.0, 2.0], [0.0, 1.0, 2.0]);
+        let mut upper = Vec::new();
+        let mut lower = Vec::new();
+
+        clip_triangle(&t, 2, 0.0, &mut upper, &mut lower, None);
+
+        assert_eq!(upper.len(), 1);
+        assert!(lower.is_empty());
+    }
+
+    #[test]
+    fn clip_triangle_straddling_plane_splits_into_both_sides() {
+        // One vertex above the z=0 plane, two below: the upper side keeps a
+        // single clipped triangle, the lower side keeps a clipped quad (two
+        // fan triangles), and the cut edge is recorded as one segment.
+        let t = triangle([0.0, 0.0, 1.0], [1.0, 0.0, -1.0], [0.0, 1.0, -1.0]);
+        let mut upper = Vec::new();
+        let mut lower = Vec::new();
+        let mut segments = Vec::new();
+
+        clip_triangle(&t, 2, 0.0, &mut upper, &mut lower, Some(&mut segments));
+
+        assert_eq!(upper.len(), 1);
+        assert_eq!(lower.len(), 2);
+        assert_eq!(segments.len(), 1);
+    }
+
+    #[test]
+    fn clip_triangle_on_plane_vertex_still_records_a_segment() {
+        // Regression test: vertex A sits exactly on the cut plane, B is
+        // above, C is below. The only crossing edge is B-C, so the segment's
+        // other endpoint has to come from A being on-plane, not from a
+        // second interpolated crossing. Dropping that endpoint used to leave
+        // `crossings.len() == 1`, so `stitch_loops` silently discarded the
+        // chain and left a hole in the cap.
+        let t = triangle([0.0, 0.0, 0.0], [1.0, 0.0, 1.0], [1.0, 1.0, -1.0]);
+        let mut upper = Vec::new();
+        let mut lower = Vec::new();
+        let mut segments = Vec::new();
+
+        clip_triangle(&t, 2, 0.0, &mut upper, &mut lower, Some(&mut segments));
+
+        assert_eq!(segments.len(), 1);
+    }
+
+    #[test]
+    fn clip_triangle_skips_near_vertex_slivers() {
+        // Edge A-B crosses the plane cleanly (t = 0.5), but edge C-A crosses
+        // at t so close to 1 that the intersection would sit on top of A
+        // itself; that near-vertex crossing must be dropped rather than
+        // turned into a zero-area sliver triangle. Dropping it leaves the
+        // upper side with only two points (no triangle), while the lower
+        // side still closes into one clean triangle.
+        let t = triangle([0.0, 0.0, 1.0], [1.0, 0.0, -1.0], [0.0, 1.0, -1_000_000.0]);
+        let mut upper = Vec::new();
+        let mut lower = Vec::new();
+
+        clip_triangle(&t, 2, 0.0, &mut upper, &mut lower, None);
+
+        assert!(upper.is_empty());
+        assert_eq!(lower.len(), 1);
+    }
+
+    #[test]
+    fn fan_triangulate_triangle_yields_one_triangle() {
+        let poly = [Vector::new([0.0, 0.0, 0.0]), Vector::new([1.0, 0.0, 0.0]), Vector::new([0.0, 1.0, 0.0])];
+        let mut out = Vec::new();
+
+        fan_triangulate(&poly, Vector::new([0.0, 0.0, 1.0]), &mut out);
+
+        assert_eq!(out.len(), 1);
+    }
+
+    #[test]
+    fn fan_triangulate_quad_yields_two_triangles() {
+        let poly = [
+            Vector::new([0.0, 0.0, 0.0]),
+            Vector::new([1.0, 0.0, 0.0]),
+            Vector::new([1.0, 1.0, 0.0]),
+            Vector::new([0.0, 1.0, 0.0]),
+        ];
+        let mut out = Vec::new();
+
+        fan_triangulate(&poly, Vector::new([0.0, 0.0, 1.0]), &mut out);
+
+        assert_eq!(out.len(), 2);
+    }
+
+    #[test]
+    fn fan_triangulate_degenerate_poly_yields_nothing() {
+        let poly = [Vector::new([0.0, 0.0, 0.0]), Vector::new([1.0, 0.0, 0.0])];
+        let mut out = Vec::new();
+
+        fan_triangulate(&poly, Vector::new([0.0, 0.0, 1.0]), &mut out);
+
+        assert!(out.is_empty());
+    }
+}
@@ -0,0 +1,197 @@
+use std::collections::HashMap;
+
+use stl_io::{Triangle, Vector};
+
+// FNV-1a: a documented, fixed 64-bit hash algorithm (unlike
+// `std::collections::hash_map::DefaultHasher`, whose algorithm is
+// explicitly unspecified and may change between Rust releases), so a
+// content hash computed here stays comparable across toolchains and time.
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+fn fnv1a(bytes: &[u8]) -> u64 {
+    let mut hash = FNV_OFFSET_BASIS;
+    for &byte in bytes {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(FNV_PRIME);
+    }
+    hash
+}
+
+/// Coordinates are quantized to this tolerance before being used as a hash
+/// key, so vertices that are only apart by float noise (e.g. from plane
+/// clipping) still weld into a single shared vertex.
+pub const DEFAULT_TOLERANCE: f32 = 1e-5;
+
+/// A flat triangle soup re-expressed as a deduplicated vertex table plus
+/// triangles stored as indices into it.
+pub struct WeldedMesh {
+    pub vertices: Vec<Vector<f32>>,
+    pub faces: Vec<[u32; 3]>,
+}
+
+impl WeldedMesh {
+    pub fn vertex_count(&self) -> usize {
+        self.vertices.len()
+    }
+
+    /// A stable 128-bit hash over the sorted, quantized vertices and faces,
+    /// so two meshes describing the same geometry hash identically
+    /// regardless of how their triangles (or welded vertices) happen to be
+    /// ordered.
+    pub fn content_hash(&self, tolerance: f32) -> u128 {
+        let quantized: Vec<[i64; 3]> = self.vertices.iter().map(|v| quantize(*v, tolerance)).collect();
+
+        // Canonical, order-independent vertex index: position in the sorted,
+        // deduplicated key list rather than the original insertion order.
+        let mut keys = quantized.clone();
+        keys.sort_unstable();
+        keys.dedup();
+        let canonical_index = |original: u32| -> u32 {
+            keys.binary_search(&quantized[original as usize]).unwrap() as u32
+        };
+
+        let mut faces: Vec<[u32; 3]> = self
+            .faces
+            .iter()
+            .map(|face| {
+                let mut face = [
+                    canonical_index(face[0]),
+                    canonical_index(face[1]),
+                    canonical_index(face[2]),
+                ];
+                face.sort_unstable();
+                face
+            })
+            .collect();
+        faces.sort_unstable();
+
+        let mut bytes = Vec::with_capacity(keys.len() * 24 + faces.len() * 12);
+        for key in &keys {
+            for component in key {
+                bytes.extend_from_slice(&component.to_le_bytes());
+            }
+        }
+        for face in &faces {
+            for index in face {
+                bytes.extend_from_slice(&index.to_le_bytes());
+            }
+        }
+
+        let low = fnv1a(&bytes);
+        // A second, independent FNV-1a pass over a distinguishable byte
+        // stream fills the high half, so it doesn't just repeat `low`.
+        bytes.push(0xff);
+        let high = fnv1a(&bytes);
+
+        ((high as u128) << 64) | low as u128
+    }
+}
+
+fn quantize(v: Vector<f32>, tolerance: f32) -> [i64; 3] {
+    [
+        (v[0] / tolerance).round() as i64,
+        (v[1] / tolerance).round() as i64,
+        (v[2] / tolerance).round() as i64,
+    ]
+}
+
+/// Build a deduplicated vertex table from a flat triangle soup, keyed by
+/// quantized coordinates, and re-express each triangle as indices into it.
+pub fn weld(triangles: &[Triangle], tolerance: f32) -> WeldedMesh {
+    let mut index_of: HashMap<[i64; 3], u32> = HashMap::new();
+    let mut vertices = Vec::new();
+    let mut faces = Vec::with_capacity(triangles.len());
+
+    for triangle in triangles {
+        let mut face = [0u32; 3];
+        for (i, vertex) in triangle.vertices.iter().enumerate() {
+            let key = quantize(*vertex, tolerance);
+            let index = *index_of.entry(key).or_insert_with(|| {
+                vertices.push(*vertex);
+                (vertices.len() - 1) as u32
+            });
+            face[i] = index;
+        }
+        faces.push(face);
+    }
+
+    WeldedMesh { vertices, faces }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn triangle(a: [f32; 3], b: [f32; 3], c: [f32; 3]) -> Triangle {
+        Triangle {
+            normal: Vector::new([0.0, 0.0, 1.0]),
+            vertices: [Vector::new(a), Vector::new(b), Vector::new(c)],
+        }
+    }
+
+    #[test]
+    fn weld_dedupes_shared_vertices() {
+        // Two triangles sharing one edge (4 distinct corners) but written as
+        // a flat 6-vertex soup, as `Mesh::save` receives them.
+        let triangles = vec![
+            triangle([0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [1.0, 1.0, 0.0]),
+            triangle([0.0, 0.0, 0.0], [1.0, 1.0, 0.0], [0.0, 1.0, 0.0]),
+        ];
+
+        let welded = weld(&triangles, DEFAULT_TOLERANCE);
+
+        assert_eq!(welded.vertex_count(), 4);
+        assert_eq!(welded.faces.len(), 2);
+        // The shared corners must resolve to the same index.
+        assert_eq!(welded.faces[0][0], welded.faces[1][0]);
+        assert_eq!(welded.faces[0][2], welded.faces[1][1]);
+    }
+
+    #[test]
+    fn quantize_welds_near_duplicate_floats_within_tolerance() {
+        let triangles = vec![
+            triangle([0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]),
+            // Same triangle again, but with float noise well inside tolerance.
+            triangle(
+                [0.0 + DEFAULT_TOLERANCE / 10.0, 0.0, 0.0],
+                [1.0, 0.0 - DEFAULT_TOLERANCE / 10.0, 0.0],
+                [0.0, 1.0, 0.0],
+            ),
+        ];
+
+        let welded = weld(&triangles, DEFAULT_TOLERANCE);
+
+        assert_eq!(welded.vertex_count(), 3);
+    }
+
+    #[test]
+    fn content_hash_ignores_vertex_and_triangle_order() {
+        let a = vec![
+            triangle([0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [1.0, 1.0, 0.0]),
+            triangle([0.0, 0.0, 0.0], [1.0, 1.0, 0.0], [0.0, 1.0, 0.0]),
+        ];
+        // Same two triangles, reordered, each with its own vertices listed
+        // in a different rotation.
+        let b = vec![
+            triangle([1.0, 1.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 0.0]),
+            triangle([1.0, 0.0, 0.0], [1.0, 1.0, 0.0], [0.0, 0.0, 0.0]),
+        ];
+
+        let hash_a = weld(&a, DEFAULT_TOLERANCE).content_hash(DEFAULT_TOLERANCE);
+        let hash_b = weld(&b, DEFAULT_TOLERANCE).content_hash(DEFAULT_TOLERANCE);
+
+        assert_eq!(hash_a, hash_b);
+    }
+
+    #[test]
+    fn content_hash_differs_for_different_geometry() {
+        let a = vec![triangle([0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0])];
+        let b = vec![triangle([0.0, 0.0, 0.0], [2.0, 0.0, 0.0], [0.0, 1.0, 0.0])];
+
+        let hash_a = weld(&a, DEFAULT_TOLERANCE).content_hash(DEFAULT_TOLERANCE);
+        let hash_b = weld(&b, DEFAULT_TOLERANCE).content_hash(DEFAULT_TOLERANCE);
+
+        assert_ne!(hash_a, hash_b);
+    }
+}